@@ -0,0 +1,97 @@
+/// ================================ ///
+///          OPTIONS :: Bump          ///
+/// ================================ ///
+/// Compute the next version from the pending changesets instead of trusting a
+/// `version` field hand-written into each changeset. We map every changeset's
+/// tag to a [`Level`], take the highest level across `.changesets`, and apply it
+/// to the current project version with SemVer semantics.
+use semver::Version;
+// Local imports
+use crate::options::Changeset;
+use crate::utilities::{
+    create_changelog, find_version, get_current_changesets, new_changelog_entry,
+};
+
+/// The kind of SemVer bump a set of changesets implies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    // Ordered from lowest to highest precedence so `max()` picks the strongest.
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Level {
+    /// Apply this level to `current`, resetting the lower components as SemVer
+    /// requires: Major → X+1.0.0, Minor → X.Y+1.0, Patch → X.Y.Z+1.
+    pub fn bump(&self, current: &Version) -> Version {
+        match self {
+            Level::Major => Version::new(current.major + 1, 0, 0),
+            Level::Minor => Version::new(current.major, current.minor + 1, 0),
+            Level::Patch => Version::new(current.major, current.minor, current.patch + 1),
+        }
+    }
+}
+
+/// Map a changeset to the bump level it implies, using both its change type and
+/// its tag. Breaking/removed changes force a Major bump, new features a Minor
+/// one, and fixes/patches/security a Patch.
+fn level_for(changeset: &Changeset) -> Level {
+    // The change type already encodes the intent, so honor it first.
+    match changeset.change.to_uppercase().as_str() {
+        "MAJOR" => return Level::Major,
+        "MINOR" => return Level::Minor,
+        "PATCH" => return Level::Patch,
+        _ => {}
+    }
+    // Fall back to the tag when the change type is missing or custom.
+    match changeset.tag.to_lowercase().as_str() {
+        "breaking" | "remove" | "removed" | "rename" | "behavior" => Level::Major,
+        "feature" | "added" | "add" => Level::Minor,
+        "fix" | "bug" | "patch" | "security" => Level::Patch,
+        _ => Level::Patch,
+    }
+}
+
+/// Infer the bump level as the highest-precedence level across all pending
+/// changesets, returning `None` when there is nothing to release.
+fn infer_level(changesets: &[Changeset]) -> Option<Level> {
+    changesets.iter().map(level_for).max()
+}
+
+pub fn bump_version() {
+    // Load every pending changeset.
+    let changesets = get_current_changesets();
+    if changesets.is_empty() {
+        println!("No pending changesets found in `.changesets`. Nothing to bump.");
+        return;
+    }
+
+    // Read the current project version and apply the inferred level.
+    let current_raw = match find_version() {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("changeforge: {}", e);
+            return;
+        }
+    };
+    let current = match Version::parse(&current_raw) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!(
+                "changeforge: the current version `{}` is not valid SemVer: {}",
+                current_raw, e
+            );
+            return;
+        }
+    };
+    let level = infer_level(&changesets).expect("Pending changesets without an inferable level");
+    let next = level.bump(&current);
+
+    // Render the accumulated changesets under the freshly computed version,
+    // promoting the [Unreleased] section and preserving prior releases.
+    let content = new_changelog_entry(&changesets, &next.to_string());
+    if let Err(e) = create_changelog(content, &next.to_string()) {
+        eprintln!("changeforge: {}", e);
+    }
+}
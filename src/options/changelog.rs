@@ -0,0 +1,71 @@
+/// ================================ ///
+///       OPTIONS :: Changelog        ///
+/// ================================ ///
+/// Roll up every pending `.changesets/*.toml` into the changelog for the next
+/// computed version. This shares the structured "Keep a Changelog" model with
+/// `bump` (see `new_changelog_entry`) so both commands write the same format.
+use crate::utilities::{
+    find_version, get_current_changesets, new_changelog_entry, write_changelog,
+    version_operations::calculate_next_version,
+};
+use crate::options::Changeset;
+
+/// Numeric precedence so we can pick the strongest change type present.
+fn rank(change: &str) -> u8 {
+    match change.to_uppercase().as_str() {
+        "MAJOR" => 3,
+        "MINOR" => 2,
+        _ => 1,
+    }
+}
+
+/// Compute the next version from the highest-precedence change type present.
+fn next_version(changesets: &[Changeset]) -> Option<String> {
+    let current = match find_version() {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("changeforge: {}", e);
+            return None;
+        }
+    };
+    let highest = changesets
+        .iter()
+        .max_by_key(|c| rank(&c.change))
+        .map(|c| c.change.to_uppercase())
+        .unwrap_or_else(|| "PATCH".to_string());
+    Some(calculate_next_version(&current, &highest))
+}
+
+/// Generate the changelog from pending changesets. In `--unreleased` preview
+/// mode the rendered document is printed to stdout and nothing is mutated.
+/// Otherwise it is written to `CHANGELOG.md` *only* — the version files are
+/// left alone, since bumping is `changeforge bump`'s job. Pass `consume` (the
+/// `--consume` flag) to also delete the changesets that were rolled up.
+pub fn generate_changelog(preview: bool, consume: bool) {
+    let changesets = get_current_changesets();
+    if changesets.is_empty() {
+        println!("No pending changesets found in `.changesets`. Nothing to generate.");
+        return;
+    }
+
+    let version = match next_version(&changesets) {
+        Some(v) => v,
+        None => return,
+    };
+
+    // Reuse the structured model: fold the changesets into [Unreleased] and
+    // promote it to a dated release, preserving prior releases below.
+    let content = new_changelog_entry(&changesets, &version);
+
+    if preview {
+        // Preview mode: print without touching any files.
+        println!("{}", content.join("\n"));
+        return;
+    }
+
+    // Render-only: write the document without bumping version files, and keep
+    // the changesets unless the caller opted into consuming them.
+    if let Err(e) = write_changelog(content, consume) {
+        eprintln!("changeforge: {}", e);
+    }
+}
@@ -11,17 +11,23 @@ use fake::faker::lorem::en::Word;
 use fake::Fake;
 use inquire::error::InquireError;
 use inquire::ui::{RenderConfig, Styled};
-use inquire::{set_global_render_config, Confirm, Select, Text};
+use inquire::{set_global_render_config, Confirm, MultiSelect, Select, Text};
 use regex::Regex;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
 use terminal_size::{terminal_size, Width};
 // Local imports
+use crate::options::extensions::{
+    ensure_extensions_loaded, extension_generate_message, extension_post_changeset,
+    extension_tags_for, has_extensions, MessageContext,
+};
 use crate::options::Changeset;
 use crate::utilities::{
     create_changeset_folder, find_version, generate_ai_message, load_changeforge_config,
-    version_operations::calculate_next_version, write_changeset_file, AIConfig,
+    validate_changeset, version_operations::calculate_next_version, write_changeset_file, AIConfig,
+    Error,
 };
 
 /// Detect modules in the project by scanning files
@@ -60,29 +66,153 @@ fn detect_modules() -> Vec<String> {
 // Get default message template based on change type and tag
 // (unused) kept templates are now read from templates_dir
 
-/// Get changed files from git
-fn get_git_changed_files() -> Vec<String> {
-    let mut changed_files = Vec::new();
+/// Added/deleted line counts for the working tree, aggregated overall and kept
+/// per path so individual modules can be weighted later.
+#[derive(Default)]
+struct GitDiffStats {
+    added: usize,
+    deleted: usize,
+    files: usize,
+    per_path: HashMap<String, (usize, usize)>,
+}
+
+impl GitDiffStats {
+    /// Sum the added/deleted counts for a set of module paths.
+    fn totals_for(&self, modules: &[String]) -> (usize, usize) {
+        modules.iter().fold((0, 0), |(a, d), m| {
+            let (pa, pd) = self.per_path.get(m).copied().unwrap_or((0, 0));
+            (a + pa, d + pd)
+        })
+    }
+}
 
-    // Try to get modified files from git
+/// Parse `git diff --numstat HEAD` into per-path churn. Binary files report
+/// `-` for both counts, which we treat as zero.
+fn git_diff_stats() -> GitDiffStats {
+    let mut stats = GitDiffStats::default();
     let output = Command::new("git")
-        .args(["diff", "--name-only", "HEAD"])
+        .args(["diff", "--numstat", "HEAD"])
         .output();
-
     if let Ok(output) = output {
         if output.status.success() {
             let git_output = String::from_utf8_lossy(&output.stdout);
             for line in git_output.lines() {
-                if !line.is_empty() {
-                    changed_files.push(line.to_string());
+                let parts: Vec<&str> = line.splitn(3, '\t').collect();
+                if parts.len() == 3 {
+                    let added = parts[0].parse::<usize>().unwrap_or(0);
+                    let deleted = parts[1].parse::<usize>().unwrap_or(0);
+                    stats.added += added;
+                    stats.deleted += deleted;
+                    stats.files += 1;
+                    stats.per_path.insert(parts[2].to_string(), (added, deleted));
                 }
             }
         }
     }
 
-    // Add "Other" option at the end
-    if !changed_files.is_empty() {
-        changed_files.push("Other (specify manually)".to_string());
+    // Untracked files are absent from `git diff` but surfaced as `??` by the
+    // module picker; fold them in as pure additions so the impact metric
+    // matches what the picker shows.
+    let untracked = Command::new("git")
+        .args(["ls-files", "--others", "--exclude-standard"])
+        .output();
+    if let Ok(untracked) = untracked {
+        if untracked.status.success() {
+            for path in String::from_utf8_lossy(&untracked.stdout).lines() {
+                if path.is_empty() {
+                    continue;
+                }
+                let added = fs::read_to_string(path)
+                    .map(|c| c.lines().count())
+                    .unwrap_or(0);
+                stats.added += added;
+                stats.files += 1;
+                stats.per_path.insert(path.to_string(), (added, 0));
+            }
+        }
+    }
+
+    stats
+}
+
+/// Suggest a change type from the overall churn, using the configured
+/// thresholds: heavy churn → MAJOR, net-positive additions → MINOR, otherwise
+/// PATCH. Returns the index into the change-type `Select`.
+fn suggested_change_index(stats: &GitDiffStats) -> usize {
+    let cfg = load_changeforge_config();
+    let churn = stats.added + stats.deleted;
+    if churn == 0 {
+        return 2; // No changes detected: default to PATCH.
+    }
+    if churn >= cfg.major_threshold {
+        0 // MAJOR
+    } else if stats.added > stats.deleted && churn >= cfg.minor_threshold {
+        1 // MINOR
+    } else {
+        2 // PATCH
+    }
+}
+
+/// A file reported by git, carrying a short status glyph for display.
+struct ChangedFile {
+    glyph: String,
+    path: String,
+}
+
+/// Derive a short status glyph from a porcelain v2 `XY` field, keeping the
+/// meaningful (non-`.`) side.
+fn glyph_from_xy(xy: &str) -> String {
+    let compact: String = xy.chars().filter(|c| *c != '.').collect();
+    if compact.is_empty() {
+        "?".to_string()
+    } else {
+        compact
+    }
+}
+
+/// Get changed files from git, classifying staged/unstaged/untracked/renamed
+/// entries via `git status --porcelain=v2`. Renamed entries are recorded under
+/// their new path.
+fn get_git_changed_files() -> Vec<ChangedFile> {
+    let mut changed_files = Vec::new();
+
+    let output = Command::new("git")
+        .args(["status", "--porcelain=v2"])
+        .output();
+
+    if let Ok(output) = output {
+        if output.status.success() {
+            let git_output = String::from_utf8_lossy(&output.stdout);
+            for line in git_output.lines() {
+                if let Some(rest) = line.strip_prefix("? ") {
+                    // Untracked file.
+                    changed_files.push(ChangedFile {
+                        glyph: "??".to_string(),
+                        path: rest.to_string(),
+                    });
+                } else if line.starts_with("1 ") {
+                    // Ordinary change: "1 XY ... <path>" (9 fields).
+                    let parts: Vec<&str> = line.splitn(9, ' ').collect();
+                    if parts.len() == 9 {
+                        changed_files.push(ChangedFile {
+                            glyph: glyph_from_xy(parts[1]),
+                            path: parts[8].to_string(),
+                        });
+                    }
+                } else if line.starts_with("2 ") {
+                    // Rename/copy: "2 XY ... <path>\t<origPath>" (10 fields).
+                    let parts: Vec<&str> = line.splitn(10, ' ').collect();
+                    if parts.len() == 10 {
+                        // The new path precedes the tab-separated original.
+                        let new_path = parts[9].split('\t').next().unwrap_or(parts[9]);
+                        changed_files.push(ChangedFile {
+                            glyph: "R".to_string(),
+                            path: new_path.to_string(),
+                        });
+                    }
+                }
+            }
+        }
     }
 
     changed_files
@@ -110,6 +240,10 @@ fn read_templates_from_dir(dir: &str) -> Vec<(String, String)> {
 
 /// Select tags depending on the change type
 fn select_tags(change_type: &str) -> Vec<String> {
+    // Let an extension override the tag set first; fall back to built-ins.
+    if let Some(specs) = extension_tags_for(change_type) {
+        return specs.iter().map(|s| s.display()).collect();
+    }
     let available_tags: Vec<String>;
     // Based on the change type representation, select the tags.
     if change_type == "MAJOR" {
@@ -159,8 +293,9 @@ fn set_tag(change_type: &str) -> (String, String) {
     (tag.to_string(), icon)
 }
 
-/// Create the questions
-fn create_name_and_type(default_name: &str) -> (String, String) {
+/// Create the questions. `suggested_index` pre-selects the change type inferred
+/// from the diff churn.
+fn create_name_and_type(default_name: &str, suggested_index: usize) -> (String, String) {
     apply_inquire_theme();
     print_note("Provide a name and select the change type for this changeset.");
     let name = Text::new("Write the Changeset name")
@@ -175,55 +310,195 @@ fn create_name_and_type(default_name: &str) -> (String, String) {
             "🩹 PATCH: Refactors, bugs, fixes and small changes.".to_string(),
         ],
     )
+    .with_starting_cursor(suggested_index)
     .prompt()
     .unwrap_or_else(|e| handle_cancel(e));
     (name, change_type)
 }
 
-/// Ask for module based on git changes and auto-detected modules
-fn ask_for_module() -> String {
-    // First try to get git changed files
+/// Ask for the modules touched by this change, allowing several to be selected
+/// at once. Candidates come from git status (with status glyphs) or, failing
+/// that, from auto-detected module files.
+fn ask_for_modules() -> Vec<String> {
+    const OTHER: &str = "Other (specify manually)";
+
+    // First try to get git changed files, annotated with their status glyph.
     let git_modules = get_git_changed_files();
 
     if !git_modules.is_empty() {
-        let choice = Select::new("Select the module/file that has changed", git_modules)
+        // Display "glyph  path" but map the selection back to the bare path.
+        let mut labels: Vec<String> = git_modules
+            .iter()
+            .map(|f| format!("{:<2} {}", f.glyph, f.path))
+            .collect();
+        labels.push(OTHER.to_string());
+
+        let chosen = MultiSelect::new("Select the module(s)/file(s) that have changed", labels)
+            .with_help_message("Use arrows/space to select, enter to confirm")
             .prompt()
             .unwrap_or_else(|e| handle_cancel(e));
-        if choice == "Other (specify manually)" {
-            Text::new("Enter the custom module name")
-                .with_default("")
-                .prompt()
-                .unwrap_or_else(|e| handle_cancel(e))
-        } else {
-            choice
+
+        let mut modules: Vec<String> = Vec::new();
+        for choice in chosen {
+            if choice == OTHER {
+                let custom = Text::new("Enter the custom module name")
+                    .with_default("")
+                    .prompt()
+                    .unwrap_or_else(|e| handle_cancel(e));
+                if !custom.trim().is_empty() {
+                    modules.push(custom);
+                }
+            } else if let Some(file) = git_modules.iter().find(|f| choice == format!("{:<2} {}", f.glyph, f.path)) {
+                modules.push(file.path.clone());
+            }
         }
+        modules
     } else {
         let detected_modules = detect_modules();
         if detected_modules.len() > 1 {
-            let choice = Select::new("Select the module that has changed", detected_modules)
+            let chosen = MultiSelect::new("Select the module(s) that have changed", detected_modules)
+                .with_help_message("Use arrows/space to select, enter to confirm")
                 .prompt()
                 .unwrap_or_else(|e| handle_cancel(e));
-            if choice == "Other (specify manually)" {
-                Text::new("Enter the custom module name")
-                    .with_default("")
-                    .prompt()
-                    .unwrap_or_else(|e| handle_cancel(e))
-            } else {
-                choice
+            let mut modules: Vec<String> = Vec::new();
+            for choice in chosen {
+                if choice == OTHER {
+                    let custom = Text::new("Enter the custom module name")
+                        .with_default("")
+                        .prompt()
+                        .unwrap_or_else(|e| handle_cancel(e));
+                    if !custom.trim().is_empty() {
+                        modules.push(custom);
+                    }
+                } else {
+                    modules.push(choice);
+                }
             }
+            modules
         } else {
-            Text::new("Write the module/class/function name that has changed (optional)")
+            let single = Text::new("Write the module/class/function name that has changed (optional)")
                 .with_default("")
                 .prompt()
-                .unwrap_or_else(|e| handle_cancel(e))
+                .unwrap_or_else(|e| handle_cancel(e));
+            if single.trim().is_empty() {
+                Vec::new()
+            } else {
+                vec![single]
+            }
         }
     }
 }
 
-/// Ask for message generation method (AI, template, manual)
+/// A draft authored in the user's editor. Any field left blank keeps the value
+/// already chosen through the interactive prompts.
+struct EditorDraft {
+    tag: Option<String>,
+    modules: Option<String>,
+    message: String,
+}
+
+/// Build the commented template handed to the editor. When a project ships its
+/// own `entry` template under `templates_dir`, that content is used verbatim;
+/// otherwise we fall back to a small built-in skeleton.
+fn editor_template(change_type: &str, tag: &str, module: &str) -> String {
+    let cfg = load_changeforge_config();
+    if let Some(dir) = cfg.templates_dir.as_ref() {
+        let entry = Path::new(dir).join("entry.txt");
+        if let Ok(content) = fs::read_to_string(&entry) {
+            return content;
+        }
+    }
+    format!(
+        "# Write the changeset message below. Lines starting with '#' are ignored.\n\
+         # You may override the pre-selected fields with `Tag:`/`Modules:` lines.\n\
+         #\n\
+         # Change type: {}\n\
+         Tag: {}\n\
+         Modules: {}\n\
+         Message:\n",
+        change_type, tag, module
+    )
+}
+
+/// Launch the user's `$VISUAL` (or `$EDITOR`, falling back to `vi`) on a
+/// temporary file seeded with `template`, wait for it to exit,
+/// and parse the saved buffer. Returns `None` when the buffer is unchanged or
+/// carries no content beyond comments, so the caller can abort cleanly.
+fn author_with_editor(change_type: &str, tag: &str, module: &str) -> Option<EditorDraft> {
+    let template = editor_template(change_type, tag, module);
+    let tmp = std::env::temp_dir().join("CHANGESET_EDITMSG");
+    if fs::write(&tmp, &template).is_err() {
+        return None;
+    }
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let status = Command::new(&editor).arg(&tmp).status();
+    if !matches!(status, Ok(s) if s.success()) {
+        let _ = fs::remove_file(&tmp);
+        return None;
+    }
+
+    let saved = fs::read_to_string(&tmp).unwrap_or_default();
+    let _ = fs::remove_file(&tmp);
+
+    // Treat an unchanged buffer as an abort.
+    if saved == template {
+        return None;
+    }
+    parse_editor_buffer(&saved)
+}
+
+/// Parse an edited buffer into an [`EditorDraft`], dropping comment-only lines
+/// and honoring optional `Tag:`/`Modules:`/`Message:` headers.
+fn parse_editor_buffer(buffer: &str) -> Option<EditorDraft> {
+    let mut tag: Option<String> = None;
+    let mut modules: Option<String> = None;
+    let mut message_lines: Vec<String> = Vec::new();
+    let mut in_message = false;
+
+    for line in buffer.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("Tag:") {
+            tag = Some(rest.trim().to_string()).filter(|s| !s.is_empty());
+        } else if let Some(rest) = line.strip_prefix("Modules:") {
+            modules = Some(rest.trim().to_string()).filter(|s| !s.is_empty());
+        } else if let Some(rest) = line.strip_prefix("Message:") {
+            in_message = true;
+            let first = rest.trim();
+            if !first.is_empty() {
+                message_lines.push(first.to_string());
+            }
+        } else if in_message {
+            message_lines.push(line.to_string());
+        } else {
+            // No explicit `Message:` header: the whole body is the message.
+            message_lines.push(line.to_string());
+        }
+    }
+
+    let message = message_lines.join("\n").trim().to_string();
+    if message.is_empty() {
+        return None;
+    }
+    Some(EditorDraft {
+        tag,
+        modules,
+        message,
+    })
+}
+
+/// Ask for message generation method (AI, editor, template, manual)
 fn ask_for_message_method() -> String {
     let cfg = load_changeforge_config();
-    let mut options: Vec<String> = vec!["Write message from scratch".to_string()];
+    let mut options: Vec<String> = vec![
+        "Author in $EDITOR".to_string(),
+        "Write message from scratch".to_string(),
+    ];
     // templates gating: require directory with at least one file
     if let Some(dir) = cfg.templates_dir.as_ref() {
         if let Ok(mut rd) = std::fs::read_dir(dir) {
@@ -236,6 +511,10 @@ fn ask_for_message_method() -> String {
     if cfg.ai_enabled {
         options.insert(0, "Generate with AI based on detected changes".to_string());
     }
+    // Extension-provided generators take the top slot when available.
+    if has_extensions() {
+        options.insert(0, "Generate with extension".to_string());
+    }
     Select::new(
         "How would you like to create your changeset message?",
         options,
@@ -244,12 +523,46 @@ fn ask_for_message_method() -> String {
     .unwrap_or_else(|e| handle_cancel(e))
 }
 
-/// Ask for the message with template suggestions
-fn ask_for_message(change_type: &str, tag: &str, module: &str) -> String {
+/// Ask for the message with template suggestions. `tag` and `modules` may be
+/// overridden when the user authors the changeset in their editor.
+fn ask_for_message(change_type: &str, tag: &mut String, modules: &mut Vec<String>) -> String {
     // First, ask which method to use
     let method = ask_for_message_method();
-
-    if method.contains("Generate with AI") {
+    let module = modules.join(", ");
+
+    if method.contains("Generate with extension") {
+        let ctx = MessageContext {
+            change_type,
+            tag: tag.as_str(),
+            modules: modules.as_slice(),
+        };
+        if let Some(message) = extension_generate_message(&ctx) {
+            return message;
+        }
+        // No extension answered: fall through to manual entry.
+        return Text::new("Write the message for the change")
+            .with_default("")
+            .prompt()
+            .unwrap_or_else(|e| handle_cancel(e));
+    } else if method.contains("Author in $EDITOR") {
+        // Seed the editor with a template and parse the saved buffer.
+        let draft = author_with_editor(change_type, tag, &module).unwrap_or_else(|| {
+            print_cancel("No changeset content provided; operation canceled");
+            std::process::exit(130);
+        });
+        if let Some(t) = draft.tag {
+            *tag = t;
+        }
+        if let Some(m) = draft.modules {
+            // A comma-separated list overrides the picked modules.
+            *modules = m
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+        draft.message
+    } else if method.contains("Generate with AI") {
         // Create AI configuration using build method
         let config = AIConfig::build();
 
@@ -259,7 +572,13 @@ fn ask_for_message(change_type: &str, tag: &str, module: &str) -> String {
         // We need to block on the async call since we're in a sync context
         let ai_message = tokio::runtime::Runtime::new()
             .unwrap()
-            .block_on(generate_ai_message(change_type, tag, module, &config))
+            .block_on(generate_ai_message(
+                change_type,
+                tag.as_str(),
+                module.as_str(),
+                &config,
+            ))
+            // `module` above is the joined list of selected modules.
             .unwrap_or_else(|e| {
                 println!("Error generating AI message: {}", e);
                 "Error generating message".to_string()
@@ -350,9 +669,36 @@ fn confirm_changeset(changeset: &Changeset) -> bool {
 }
 
 fn process_answers() -> Changeset {
+    let validation = load_changeforge_config().validation;
+    loop {
+        let changeset = build_changeset();
+        // Validate against the configured rules before offering to save.
+        if let Err(violations) = validate_changeset(&changeset, &validation) {
+            print_cancel_box("The changeset did not pass validation:");
+            for violation in &violations {
+                println!("  {} {}", "•".red(), violation.red());
+            }
+            println!();
+            // Re-prompt from the top instead of saving an invalid changeset.
+            continue;
+        }
+        // Return the changeset only if confirmed
+        if confirm_changeset(&changeset) {
+            return changeset;
+        } else {
+            print_cancel("Operation canceled by user");
+            std::process::exit(130);
+        }
+    }
+}
+
+fn build_changeset() -> Changeset {
+    // Gather diff churn up front so we can suggest a change type.
+    let stats = git_diff_stats();
     // Generate the default name
     let default_name = "Leave it blank for a random name";
-    let (mut name, selected_change) = create_name_and_type(default_name);
+    let (mut name, selected_change) =
+        create_name_and_type(default_name, suggested_change_index(&stats));
     if name == default_name || name.trim().is_empty() {
         name = Word().fake();
     }
@@ -367,43 +713,54 @@ fn process_answers() -> Changeset {
     }
 
     // Get the tag (now that we know the change type)
-    let (tag, _tag_icon) = set_tag(change);
+    let (mut tag, _tag_icon) = set_tag(change);
 
-    // Get the module (with git and auto-detection)
-    let module = ask_for_module();
+    // Get the modules (with git status and auto-detection, multi-select)
+    let mut modules = ask_for_modules();
 
-    // Get the message (with AI, templates, or manual input)
-    let message = ask_for_message(change, &tag, &module);
+    // Get the message (with AI, editor, templates, or manual input). The editor
+    // path may override the tag/modules chosen above.
+    let message = ask_for_message(change, &mut tag, &mut modules);
 
     // Get the current version
-    let current_version = find_version();
+    let current_version = find_version().unwrap_or_else(|e| {
+        print_cancel(&format!("{}", e));
+        std::process::exit(1);
+    });
 
     // Calculate the next version based on the change type
     let next_version = calculate_next_version(&current_version, change);
 
+    // Aggregate the churn across the selected modules and store it so later
+    // changelog/version tooling can weight this entry.
+    let (insertions, deletions) = stats.totals_for(&modules);
+
     // Create the changeset
-    let changeset = Changeset {
+    Changeset {
         name,
         change: change.into(),
-        modules: module,
+        modules,
         tag,
         message,
         version: next_version,
-    };
+        insertions: Some(insertions),
+        deletions: Some(deletions),
+    }
+}
 
-    // Return the changeset only if confirmed
-    if confirm_changeset(&changeset) {
-        // Attach icon info via side channel by returning after commit stage
-        // We'll perform commit in create_changesets where we still know module path
-        // For now return the built changeset
-        changeset
-    } else {
-        print_cancel("Operation canceled by user");
-        std::process::exit(130);
+/// Run a `git` subcommand, reporting a spawn failure through [`Error::Subprocess`].
+fn run_git(args: &[String]) {
+    if let Err(e) = Command::new("git").args(args).status() {
+        eprintln!(
+            "changeforge: {}",
+            Error::Subprocess(format!("git {}", args.join(" ")), e)
+        );
     }
 }
 
 pub fn create_changesets() {
+    // Load any config-enabled extensions before the prompt flow consults them.
+    ensure_extensions_loaded();
     // Process the results
     let changeset: Changeset = process_answers();
     // Then, start creating the Changeset file in the changeset function
@@ -411,6 +768,8 @@ pub fn create_changesets() {
     create_changeset_folder();
     // Once you have created the folder, create the changeset
     write_changeset_file(&changeset);
+    // Let extensions react to the freshly saved changeset.
+    extension_post_changeset(&changeset);
     // Optional commit on create
     let cfg = load_changeforge_config();
     if cfg.commit_on_create {
@@ -442,14 +801,17 @@ pub fn create_changesets() {
             // Paths to add
             let mut paths: Vec<String> = Vec::new();
             paths.push(format!(".changesets/{}.toml", changeset.name));
-            if !changeset.modules.is_empty() && Path::new(&changeset.modules).exists() {
-                paths.push(changeset.modules.clone());
+            // Stage every selected module that maps to an existing path.
+            for module in &changeset.modules {
+                if Path::new(module).exists() {
+                    paths.push(module.clone());
+                }
             }
-            // Run git add and commit
-            let _ = Command::new("git").args(["add"]).args(&paths).status();
-            let _ = Command::new("git")
-                .args(["commit", "-m", &commit_msg])
-                .status();
+            // Run git add and commit, surfacing spawn failures cleanly.
+            let mut add_args: Vec<String> = vec!["add".to_string()];
+            add_args.extend(paths.iter().cloned());
+            run_git(&add_args);
+            run_git(&["commit".to_string(), "-m".to_string(), commit_msg]);
         }
     }
     // Once you have created it, print a confirmation message
@@ -544,7 +906,12 @@ fn print_summary_box(changeset: &Changeset) {
     lines.push(format!("Type: {}", changeset.change));
     lines.push(format!("Tag: {}", changeset.tag));
     if !changeset.modules.is_empty() {
-        lines.push(format!("Module: {}", changeset.modules));
+        lines.push(format!("Modules: {}", changeset.modules.join(", ")));
+    }
+    if let (Some(ins), Some(del)) = (changeset.insertions, changeset.deletions) {
+        if ins > 0 || del > 0 {
+            lines.push(format!("Churn: +{} / -{}", ins, del));
+        }
     }
     lines.push(format!("Message: {}", changeset.message));
     lines.push(format!("Version: {}", changeset.version));
@@ -0,0 +1,128 @@
+/// ================================ ///
+///      OPTIONS :: Extensions        ///
+/// ================================ ///
+/// Pluggable hooks for the changeset-creation flow. Extensions can contribute
+/// their own tag sets, generate messages, and react once a changeset is saved.
+/// The registry iterates every registered extension and lets the first one that
+/// answers win, falling back to the built-in behavior otherwise.
+///
+/// Registration is compile-time today (a built-in default plus anything added
+/// via [`register_extension`]), but the trait is deliberately object-safe so an
+/// out-of-process or dynamically loaded backend can slot in later.
+use std::sync::{Mutex, OnceLock};
+// Local imports
+use crate::options::Changeset;
+
+/// A single selectable tag: its icon, the cleaned word stored on the changeset,
+/// and the human description shown in the prompt.
+pub struct TagSpec {
+    pub icon: String,
+    pub word: String,
+    pub description: String,
+}
+
+impl TagSpec {
+    /// The display string fed to the selection prompt, matching the built-in
+    /// `"<icon> <Word>: <description>."` shape so tag parsing stays uniform.
+    pub fn display(&self) -> String {
+        format!("{} {}: {}", self.icon, self.word, self.description)
+    }
+}
+
+/// Context handed to message generators.
+pub struct MessageContext<'a> {
+    pub change_type: &'a str,
+    pub tag: &'a str,
+    pub modules: &'a [String],
+}
+
+/// The extension hook surface. Every method has a no-op/`None` default so
+/// implementors only override what they care about.
+pub trait ChangeForgeExtension: Send + Sync {
+    /// Override the tag set offered for a change type.
+    fn tags_for(&self, _change_type: &str) -> Option<Vec<TagSpec>> {
+        None
+    }
+    /// Generate a changeset message from the given context.
+    fn generate_message(&self, _ctx: &MessageContext) -> Option<String> {
+        None
+    }
+    /// React to a changeset once it has been saved (e.g. ticket integration).
+    fn post_changeset(&self, _changeset: &Changeset) {}
+}
+
+fn registry() -> &'static Mutex<Vec<Box<dyn ChangeForgeExtension>>> {
+    static REGISTRY: OnceLock<Mutex<Vec<Box<dyn ChangeForgeExtension>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Set once the built-in default has been registered.
+static LOADED: OnceLock<()> = OnceLock::new();
+
+/// Register an extension. Intended to be called once during startup.
+pub fn register_extension(extension: Box<dyn ChangeForgeExtension>) {
+    if let Ok(mut reg) = registry().lock() {
+        // Keep the built-in default last so it only answers as a fallback.
+        let at = if LOADED.get().is_some() {
+            reg.len().saturating_sub(1)
+        } else {
+            reg.len()
+        };
+        reg.insert(at, extension);
+    }
+}
+
+/// The built-in default extension. It ships a message generator so the
+/// "Generate with extension" path has something to do out of the box, and
+/// leaves the other hooks at their defaults. Third-party extensions registered
+/// afterwards take precedence, since the first answer wins.
+struct BuiltinExtension;
+
+impl ChangeForgeExtension for BuiltinExtension {
+    fn generate_message(&self, ctx: &MessageContext) -> Option<String> {
+        // A conventional, deterministic summary the author can accept or tweak.
+        let scope = if ctx.modules.is_empty() {
+            String::new()
+        } else {
+            format!("{}: ", ctx.modules.join(", "))
+        };
+        Some(format!("{}{} {} change", scope, ctx.tag, ctx.change_type))
+    }
+}
+
+/// Resolve the enabled extensions before the prompt flow consults the registry.
+///
+/// Registration is compile-time today: the built-in default is registered once,
+/// and additional extensions can be added via [`register_extension`]. The hook
+/// is idempotent so the creation flow can call it unconditionally.
+pub fn ensure_extensions_loaded() {
+    LOADED.get_or_init(|| {
+        register_extension(Box::new(BuiltinExtension));
+    });
+}
+
+/// First extension to answer wins; `None` means "fall back to built-ins".
+pub fn extension_tags_for(change_type: &str) -> Option<Vec<TagSpec>> {
+    let reg = registry().lock().ok()?;
+    reg.iter().find_map(|ext| ext.tags_for(change_type))
+}
+
+/// First extension to produce a message wins.
+pub fn extension_generate_message(ctx: &MessageContext) -> Option<String> {
+    let reg = registry().lock().ok()?;
+    reg.iter().find_map(|ext| ext.generate_message(ctx))
+}
+
+/// Notify every extension that a changeset was saved.
+pub fn extension_post_changeset(changeset: &Changeset) {
+    if let Ok(reg) = registry().lock() {
+        for ext in reg.iter() {
+            ext.post_changeset(changeset);
+        }
+    }
+}
+
+/// Whether any extension is registered, used to gate extension-only prompts.
+pub fn has_extensions() -> bool {
+    registry().lock().map(|r| !r.is_empty()).unwrap_or(false)
+}
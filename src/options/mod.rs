@@ -1,12 +1,17 @@
 // Import the files
 mod bump;
+mod changelog;
 mod changeset;
 mod create;
+pub mod extensions;
 mod init;
 mod list;
+mod validate;
 // Make them public
 pub use bump::bump_version;
+pub use changelog::generate_changelog;
 pub use changeset::Changeset;
 pub use create::create_changesets;
 pub use init::init_project;
 pub use list::list_changesets;
+pub use validate::validate_changesets;
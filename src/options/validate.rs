@@ -0,0 +1,42 @@
+/// ================================ ///
+///        OPTIONS :: Validate        ///
+/// ================================ ///
+/// Walk the pending `.changesets/*.toml` files and check each one against the
+/// configured validation rules, so CI can reject malformed changesets before
+/// they are ever released.
+use colored::*;
+// Local imports
+use crate::utilities::{get_current_changesets, load_changeforge_config, validate_changeset};
+
+/// Validate every pending changeset. Returns the number of offending
+/// changesets so the CLI can translate it into a non-zero exit code.
+pub fn validate_changesets() -> usize {
+    let validation = load_changeforge_config().validation;
+    let changesets = get_current_changesets();
+
+    if changesets.is_empty() {
+        println!("No pending changesets found in `.changesets`.");
+        return 0;
+    }
+
+    let mut failures = 0usize;
+    for changeset in &changesets {
+        match validate_changeset(changeset, &validation) {
+            Ok(()) => println!("{} {}.toml", "✔".green(), changeset.name),
+            Err(violations) => {
+                failures += 1;
+                println!("{} {}.toml", "✖".red(), changeset.name.red());
+                for violation in &violations {
+                    println!("  {} {}", "•".red(), violation.red());
+                }
+            }
+        }
+    }
+
+    if failures == 0 {
+        println!("\nAll {} changeset(s) are valid.", changesets.len());
+    } else {
+        println!("\n{} of {} changeset(s) failed validation.", failures, changesets.len());
+    }
+    failures
+}
@@ -1,17 +1,22 @@
+use chrono::Local;
 use colored::*;
-use std::collections::HashSet;
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
 use terminal_size::{terminal_size, Width};
 // Local imports
 use crate::options::Changeset;
-use crate::utilities::update_version_path;
+use crate::utilities::{update_version_path, Error, Result};
 
 /// Function to open the Changeset in case that exists
 pub fn open_changelog() -> Vec<String> {
-    // Open the Changeset file in case that exist
-    let file = fs::File::open("CHANGELOG.md")
-        .expect("Error opening CHANGELOG.md. Ensure that you have one already.");
+    // Open the CHANGELOG file in case it exists; an absent file is not an error,
+    // it simply means we start from an empty document.
+    let file = match fs::File::open("CHANGELOG.md") {
+        Ok(file) => file,
+        Err(_) => return Vec::new(),
+    };
     let reader = BufReader::new(file);
 
     // Create the content structure
@@ -26,74 +31,314 @@ pub fn open_changelog() -> Vec<String> {
     content
 }
 
-pub fn create_changelog(content: Vec<String>, version: &str) {
-    // Create a new CHANGELOG.md file
-    let mut file = fs::File::create("CHANGELOG.md").expect("Error creating the CHANGELOG.md");
+/// The canonical "Keep a Changelog" categories, in the order they are rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+}
 
-    // Write the entire CHANGELOG content
-    writeln!(file, "{}", content.join("\n")).expect("Error when writing the CHANGELOG.md");
-    // Write the new version file too
-    update_version_path(version);
-    // Delete all the current changesets
-    delete_changesets();
-    // If everything's cool, then write the successful message (styled)
-    print_success_box("CHANGELOG.md and version updated!");
+impl Category {
+    fn title(&self) -> &'static str {
+        match self {
+            Category::Added => "Added",
+            Category::Changed => "Changed",
+            Category::Deprecated => "Deprecated",
+            Category::Removed => "Removed",
+            Category::Fixed => "Fixed",
+            Category::Security => "Security",
+        }
+    }
+
+    fn from_title(title: &str) -> Option<Category> {
+        match title.trim() {
+            "Added" => Some(Category::Added),
+            "Changed" => Some(Category::Changed),
+            "Deprecated" => Some(Category::Deprecated),
+            "Removed" => Some(Category::Removed),
+            "Fixed" => Some(Category::Fixed),
+            "Security" => Some(Category::Security),
+            _ => None,
+        }
+    }
 }
 
-pub fn new_changelog_entry(changesets: &[Changeset], version: &String) -> Vec<String> {
-    // Update the version based on the latest
-    // First, get a list of printed tags to avoid read the same tag twice
-    let mut printed_tags: HashSet<&String> = HashSet::new();
-    // Create a mutable for the content written
-    let mut content: Vec<String> = Vec::new();
-    content.push(format!("## [{}]\n", version));
-    for changeset in changesets.iter() {
-        // Evaluate if this tag has been written
-        if printed_tags.contains(&changeset.tag) {
-            continue;
+/// Map a changeset tag to the Keep a Changelog category it belongs to.
+fn category_for_tag(tag: &str) -> Category {
+    match tag.to_lowercase().as_str() {
+        "remove" | "removed" => Category::Removed,
+        "feature" | "add" | "added" => Category::Added,
+        "deprecated" => Category::Deprecated,
+        "bug" | "patch" | "tests" | "fix" => Category::Fixed,
+        "security" => Category::Security,
+        // Renames, behavior changes, refactors and I/O tweaks are all "Changed".
+        _ => Category::Changed,
+    }
+}
+
+/// A single release block: a version, an optional date, and its categorized
+/// bullet lists. The `[Unreleased]` section is modeled as a release with no date.
+#[derive(Default)]
+struct Release {
+    version: String,
+    date: Option<String>,
+    sections: BTreeMap<Category, Vec<String>>,
+}
+
+impl Release {
+    fn push(&mut self, category: Category, entry: String) {
+        self.sections.entry(category).or_default().push(entry);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.sections.values().all(|v| v.is_empty())
+    }
+
+    fn render(&self, into: &mut Vec<String>) {
+        match &self.date {
+            Some(date) => into.push(format!("## [{}] - {}", self.version, date)),
+            None => into.push(format!("## [{}]", self.version)),
         }
-        // Write the tag first
-        content.push(format!("\n### {}\n\n", changeset.tag));
-        // Filter for all the same tags
-        for nested_changeset in changesets.iter().filter(|c| c.tag == changeset.tag) {
-            // Then, write all the changes
-            if nested_changeset.modules.is_empty() {
-                content.push(format!("- {}.\n", nested_changeset.message));
-            } else {
-                content.push(format!(
-                    "- {}: {}.\n",
-                    nested_changeset.modules, nested_changeset.message
-                ));
+        for (category, entries) in &self.sections {
+            if entries.is_empty() {
+                continue;
+            }
+            into.push(String::new());
+            into.push(format!("### {}", category.title()));
+            for entry in entries {
+                into.push(format!("- {}", entry));
             }
         }
-        // And at the end, write this tag on the read ones
-        printed_tags.insert(&changeset.tag);
+        into.push(String::new());
     }
-    // And at the end, return the content list
-    content
 }
 
-fn delete_changesets() {
-    let folder_path = ".changesets";
-    // Verify if the folder exist
-    if let Ok(entries) = fs::read_dir(folder_path) {
-        // Iterate over all the changesets in that folder
-        for entry in entries.flatten() {
-            let path = entry.path();
-            // For security, verify if the entry is a file
-            if path.is_file() {
-                // Try to remove the file
-                if let Err(e) = fs::remove_file(&path) {
-                    // If you could not delete a file, then panic
-                    panic!("Error deleting file {}: {}", path.display(), e);
+/// The parsed changelog: a persistent `[Unreleased]` section plus every prior
+/// dated release, preserved in file order.
+struct Changelog {
+    unreleased: Release,
+    releases: Vec<Release>,
+    /// Link-reference definitions from the bottom of the file, kept verbatim.
+    link_refs: Vec<String>,
+}
+
+impl Changelog {
+    /// Parse an existing `CHANGELOG.md` into structured releases. Lines that
+    /// don't belong to a recognized section (such as a hand-edited header) are
+    /// ignored; `render` writes the standard header in their place.
+    fn parse(lines: &[String]) -> Changelog {
+        let mut unreleased = Release {
+            version: "Unreleased".to_string(),
+            ..Release::default()
+        };
+        let mut releases: Vec<Release> = Vec::new();
+        // `current` is None until we enter the first release heading; while we
+        // are inside the Unreleased block we accumulate straight into it.
+        let mut current: Option<Release> = None;
+        let mut in_unreleased = false;
+        let mut category: Option<Category> = None;
+        let mut link_refs: Vec<String> = Vec::new();
+
+        for line in lines {
+            let trimmed = line.trim();
+            if let Some(rest) = trimmed.strip_prefix("## ") {
+                // Flush the release we were building.
+                if let Some(rel) = current.take() {
+                    releases.push(rel);
+                }
+                category = None;
+                let (version, date) = parse_release_heading(rest);
+                if version.eq_ignore_ascii_case("unreleased") {
+                    in_unreleased = true;
+                } else {
+                    in_unreleased = false;
+                    current = Some(Release {
+                        version,
+                        date,
+                        sections: BTreeMap::new(),
+                    });
                 }
+            } else if let Some(rest) = trimmed.strip_prefix("### ") {
+                // Canonical headings map straight through; legacy ChangeForge
+                // headings (e.g. `### Bug`, `### Feature`) are migrated into the
+                // matching Keep a Changelog category instead of being dropped,
+                // so a truncate-and-rewrite never loses their bullets.
+                category = Some(Category::from_title(rest).unwrap_or_else(|| category_for_tag(rest)));
+            } else if trimmed.starts_with('[') && trimmed.contains("]:") {
+                // Bottom-of-file link-reference definitions (`[1.2.0]: <url>`)
+                // carry no bullet content; keep them verbatim and re-emit them
+                // after the releases so comparison links survive a round-trip.
+                link_refs.push(line.clone());
+            } else if let Some(rest) = trimmed.strip_prefix("- ") {
+                if let Some(cat) = category {
+                    if in_unreleased {
+                        unreleased.push(cat, rest.to_string());
+                    } else if let Some(rel) = current.as_mut() {
+                        rel.push(cat, rest.to_string());
+                    }
+                }
+            }
+        }
+        if let Some(rel) = current.take() {
+            releases.push(rel);
+        }
+
+        Changelog {
+            unreleased,
+            releases,
+            link_refs,
+        }
+    }
+
+    /// Render the whole document back to lines, starting with the standard
+    /// header and a persistent `[Unreleased]` section.
+    fn render(&self) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        out.push("# Changelog".to_string());
+        out.push(String::new());
+        out.push(
+            "All notable changes to this project will be documented in this file.".to_string(),
+        );
+        out.push(String::new());
+
+        // The Unreleased section is always present, even when empty.
+        out.push("## [Unreleased]".to_string());
+        for (category, entries) in &self.unreleased.sections {
+            if entries.is_empty() {
+                continue;
             }
+            out.push(String::new());
+            out.push(format!("### {}", category.title()));
+            for entry in entries {
+                out.push(format!("- {}", entry));
+            }
+        }
+        out.push(String::new());
+
+        for release in &self.releases {
+            release.render(&mut out);
+        }
+
+        // Re-emit any link-reference definitions we parsed, so comparison URLs
+        // at the foot of the document outlive the rewrite.
+        if !self.link_refs.is_empty() {
+            for link in &self.link_refs {
+                out.push(link.clone());
+            }
+        }
+        out
+    }
+}
+
+/// Split a `## ` heading body into its version and optional date, handling both
+/// `[x.y.z] - YYYY-MM-DD` and bare `[Unreleased]` forms.
+fn parse_release_heading(rest: &str) -> (String, Option<String>) {
+    let rest = rest.trim();
+    // Pull the version out of the `[...]` brackets first so a prerelease dash
+    // (e.g. `1.2.0-rc.1`) is never mistaken for the date separator.
+    match rest.split_once(']') {
+        Some((version_part, after)) => {
+            let version = version_part.trim_start_matches('[').trim().to_string();
+            // Only look for the date in what follows the closing bracket.
+            let date = after
+                .trim()
+                .trim_start_matches('-')
+                .trim()
+                .to_string();
+            let date = if date.is_empty() { None } else { Some(date) };
+            (version, date)
+        }
+        None => (rest.to_string(), None),
+    }
+}
+
+/// Fold the pending changesets into a structured changelog, promote the
+/// `[Unreleased]` section into a dated `## [version] - YYYY-MM-DD` release,
+/// and render the full document (prior releases preserved below).
+pub fn new_changelog_entry(changesets: &[Changeset], version: &str) -> Vec<String> {
+    let existing = open_changelog();
+    let mut changelog = Changelog::parse(&existing);
+
+    // Route every changeset into its matching category within Unreleased.
+    for changeset in changesets {
+        let category = category_for_tag(&changeset.tag);
+        let entry = if changeset.modules.is_empty() {
+            format!("{}.", changeset.message)
+        } else {
+            format!("{}: {}.", changeset.modules.join(", "), changeset.message)
+        };
+        changelog.unreleased.push(category, entry);
+    }
+
+    // Promote Unreleased to a dated release, leaving a fresh empty Unreleased.
+    let mut released = std::mem::take(&mut changelog.unreleased);
+    released.version = version.to_string();
+    released.date = Some(Local::now().format("%Y-%m-%d").to_string());
+    changelog.unreleased = Release {
+        version: "Unreleased".to_string(),
+        ..Release::default()
+    };
+    if !released.is_empty() {
+        changelog.releases.insert(0, released);
+    }
+
+    changelog.render()
+}
+
+/// Write the rendered changelog to `CHANGELOG.md` without bumping any version
+/// files. When `consume` is set, the pending changesets are deleted after a
+/// successful write; otherwise they are left untouched so the aggregation can
+/// be re-run or reviewed before release. This is the path used by
+/// `changeforge changelog`, where rendering must stay independent of the bump.
+pub fn write_changelog(content: Vec<String>, consume: bool) -> Result<()> {
+    let changelog = PathBuf::from("CHANGELOG.md");
+    // Create a new CHANGELOG.md file
+    let mut file = fs::File::create(&changelog).map_err(|e| Error::Io(changelog.clone(), e))?;
+
+    // Write the entire CHANGELOG content
+    writeln!(file, "{}", content.join("\n")).map_err(|e| Error::Io(changelog.clone(), e))?;
+    // Only drop the changesets when the caller explicitly opts in.
+    if consume {
+        delete_changesets()?;
+    }
+    print_success_box("CHANGELOG.md updated!");
+    Ok(())
+}
+
+pub fn create_changelog(content: Vec<String>, version: &str) -> Result<()> {
+    let changelog = PathBuf::from("CHANGELOG.md");
+    // Create a new CHANGELOG.md file
+    let mut file = fs::File::create(&changelog).map_err(|e| Error::Io(changelog.clone(), e))?;
+
+    // Write the entire CHANGELOG content
+    writeln!(file, "{}", content.join("\n")).map_err(|e| Error::Io(changelog.clone(), e))?;
+    // Write the new version file too
+    update_version_path(version)?;
+    // Delete all the current changesets
+    delete_changesets()?;
+    // If everything's cool, then write the successful message (styled)
+    print_success_box("CHANGELOG.md and version updated!");
+    Ok(())
+}
+
+fn delete_changesets() -> Result<()> {
+    let folder_path = PathBuf::from(".changesets");
+    // Read the folder; surface a clean error if it's missing instead of aborting.
+    let entries = fs::read_dir(&folder_path).map_err(|e| Error::Io(folder_path.clone(), e))?;
+    // Iterate over all the changesets in that folder
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // For security, verify if the entry is a file
+        if path.is_file() {
+            // Try to remove the file
+            fs::remove_file(&path).map_err(|e| Error::Io(path.clone(), e))?;
         }
-    } else {
-        // In this case, panic. It should only reach to this function in case that
-        // the folder `.changeset` exists
-        panic!("The folder {} does not exist.", folder_path);
     }
+    Ok(())
 }
 
 fn print_success_box(message: &str) {
@@ -0,0 +1,28 @@
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Crate-wide error type. Every variant carries enough context — above all the
+/// offending [`PathBuf`] — for the CLI layer to print a single clean diagnostic
+/// line instead of aborting the process with a `panic!` stack dump.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// An I/O operation failed on a specific file.
+    #[error("{0}: {1}")]
+    Io(PathBuf, #[source] io::Error),
+
+    /// A TOML document could not be parsed.
+    #[error("{0}: invalid TOML: {1}")]
+    TomlParse(PathBuf, #[source] toml::de::Error),
+
+    /// The expected version field was not found in the file.
+    #[error("{0}: could not find a version number")]
+    VersionNotFound(PathBuf),
+
+    /// A spawned subprocess (e.g. `git`) failed to run.
+    #[error("subprocess `{0}` failed: {1}")]
+    Subprocess(String, #[source] io::Error),
+}
+
+/// Convenience alias used across the crate.
+pub type Result<T> = std::result::Result<T, Error>;
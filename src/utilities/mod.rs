@@ -3,11 +3,13 @@ pub mod ai_calls;
 pub mod ai_message_generator;
 pub mod changelog_utils;
 pub mod changeset_structures;
+pub mod error;
 pub mod version_operations;
 
 // Re-exports
 pub use ai_message_generator::{generate_ai_message, AIConfig};
-pub use changelog_utils::{create_changelog, new_changelog_entry, open_changelog};
+pub use changelog_utils::{create_changelog, new_changelog_entry, open_changelog, write_changelog};
+pub use error::{Error, Result};
 
 /// Make the modules accessible
 mod changesets_utilities;
@@ -20,31 +22,74 @@ pub use sets_utils::{create_changeset_folder, write_changeset_file};
 pub use subcommands::create_subcommands;
 // Libraries to use
 use regex::Regex;
+use semver::Version;
 use std::fs;
-use std::io::{BufRead, BufReader, Read, Write};
+use std::io::Write;
+use std::path::PathBuf;
 use toml::Value;
 
-pub fn find_version() -> String {
+/// The SemVer core plus optional prerelease/build, shared by every built-in
+/// version pattern so capture group 1 is always the full version string.
+const VERSION_CORE: &str = r"\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?";
+
+/// A version file together with the regex used to both read and rewrite its
+/// version. The regex must expose the version in capture group 1.
+pub struct VersionFile {
+    pub path: String,
+    pub pattern: Regex,
+}
+
+/// Resolve a named built-in format into its capture regex. Unknown names fall
+/// back to the `generic` heuristic (double-quoted `version`/`__version__`).
+fn builtin_pattern(format: &str) -> String {
+    match format {
+        "cargo" => format!(r#"(?m)^\s*version\s*=\s*"({})""#, VERSION_CORE),
+        "npm" => format!(r#""version"\s*:\s*"({})""#, VERSION_CORE),
+        "python" => format!(r#"(?:__version__|version)\s*=\s*['"]({})['"]"#, VERSION_CORE),
+        // generic
+        _ => format!(r#"(?:__version__|version)\s*=\s*"({})""#, VERSION_CORE),
+    }
+}
+
+/// Build a [`VersionFile`] from a `version_path` entry, which may be either a
+/// bare string or a table carrying `path` plus `format`/`pattern`.
+fn version_file_from_value(entry: &Value) -> Option<VersionFile> {
+    let (path, pattern_src) = if let Some(s) = entry.as_str() {
+        (s.to_string(), builtin_pattern("generic"))
+    } else if let Some(table) = entry.as_table() {
+        let path = table.get("path").and_then(|v| v.as_str())?.to_string();
+        // An explicit `pattern` wins over a named `format`.
+        let src = if let Some(p) = table.get("pattern").and_then(|v| v.as_str()) {
+            p.to_string()
+        } else {
+            let fmt = table.get("format").and_then(|v| v.as_str()).unwrap_or("generic");
+            builtin_pattern(fmt)
+        };
+        (path, src)
+    } else {
+        return None;
+    };
+    let pattern = Regex::new(&pattern_src).ok()?;
+    Some(VersionFile { path, pattern })
+}
+
+pub fn find_version() -> Result<String> {
     // Find the version in the current path
-    let version_paths = find_version_in_file();
-    // Using this, return the version
-    open_path(version_paths[0].clone())
+    let version_files = find_version_in_file()?;
+    // Using this, return the version from the first file
+    open_path(&version_files[0])
 }
 
-pub fn find_version_in_file() -> Vec<String> {
+pub fn find_version_in_file() -> Result<Vec<VersionFile>> {
     // Prefer standalone changeforge.toml
     if let Ok(cfg) = fs::read_to_string("changeforge.toml") {
         if let Ok(toml_cfg) = cfg.parse::<Value>() {
             if let Some(cf) = toml_cfg.get("changeforge") {
-                if let Some(possible_paths) = cf.get("version_path") {
-                    if let Some(paths) = possible_paths.as_array() {
-                        let mut version_paths: Vec<String> = Vec::new();
-                        for path in paths {
-                            version_paths.push(path.to_string().replace("\"", ""));
-                        }
-                        if !version_paths.is_empty() {
-                            return version_paths;
-                        }
+                if let Some(paths) = cf.get("version_path").and_then(|v| v.as_array()) {
+                    let files: Vec<VersionFile> =
+                        paths.iter().filter_map(version_file_from_value).collect();
+                    if !files.is_empty() {
+                        return Ok(files);
                     }
                 }
             }
@@ -52,42 +97,135 @@ pub fn find_version_in_file() -> Vec<String> {
     }
 
     // Fallback to pyproject.toml [tool.changeforge]
-    let route = "pyproject.toml";
-    let config = fs::read_to_string(route).expect("Error reading the `pyproject.toml` file");
+    let route = PathBuf::from("pyproject.toml");
+    let config = fs::read_to_string(&route).map_err(|e| Error::Io(route.clone(), e))?;
     let toml_config: Value = config
         .parse()
-        .unwrap_or_else(|e| panic!("Error getting the file {}: {}", route, e));
+        .map_err(|e| Error::TomlParse(route.clone(), e))?;
 
-    let mut version_paths: Vec<String> = Vec::new();
-    if let Some(tool) = toml_config.get("tool") {
-        if let Some(changeforge) = tool.get("changeforge") {
-            if let Some(possible_paths) = changeforge.get("version_path") {
-                if let Some(paths) = possible_paths.as_array() {
-                    for path in paths {
-                        version_paths.push(path.to_string().replace("\"", ""));
-                    }
-                } else {
-                    panic!("The version path doesn't include a path");
-                }
-            } else {
-                panic!("The changeforge utility doesn't include a `version_path` field")
-            }
-        } else {
-            panic!("The pyproject doesn't have changeforge as tool. You should have [tool.changeforge].")
-        }
-    } else {
-        panic!("The pyproject doesn't have tools associated. Please add the `changeforge` tool as [tool.changeforge].")
-    }
-    if version_paths.is_empty() {
-        panic!("Couldn't find any version paths in the configuration.")
+    let files: Vec<VersionFile> = toml_config
+        .get("tool")
+        .and_then(|t| t.get("changeforge"))
+        .and_then(|cf| cf.get("version_path"))
+        .and_then(|v| v.as_array())
+        .map(|paths| paths.iter().filter_map(version_file_from_value).collect())
+        .unwrap_or_default();
+
+    if files.is_empty() {
+        return Err(Error::VersionNotFound(route));
     }
-    version_paths
+    Ok(files)
 }
 
 pub struct CFConfig {
     pub ai_enabled: bool,
     pub templates_dir: Option<String>,
     pub commit_on_create: bool,
+    pub validation: ValidationConfig,
+    /// Total churn at or above which a change is suggested as MAJOR.
+    pub major_threshold: usize,
+    /// Total churn at or above which a net-additive change is suggested as MINOR.
+    pub minor_threshold: usize,
+}
+
+impl Default for CFConfig {
+    fn default() -> Self {
+        CFConfig {
+            ai_enabled: true,
+            templates_dir: None,
+            commit_on_create: true,
+            validation: ValidationConfig::default(),
+            major_threshold: 200,
+            minor_threshold: 20,
+        }
+    }
+}
+
+/// Rules that a changeset must satisfy before it is saved or accepted by CI.
+/// An unset numeric rule (0) disables that particular check.
+#[derive(Default)]
+pub struct ValidationConfig {
+    pub max_subject_len: usize,
+    pub min_message_len: usize,
+    pub require_module: bool,
+    pub forbidden_patterns: Vec<String>,
+    pub allowed_tags_per_type: std::collections::HashMap<String, Vec<String>>,
+}
+
+/// Build a [`CFConfig`] from the `[changeforge]` table value.
+fn cf_config_from_value(cf: &Value) -> CFConfig {
+    let ai_enabled = cf.get("ai_enabled").and_then(|v| v.as_bool()).unwrap_or(true);
+    let templates_dir = cf
+        .get("templates_dir")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .filter(|s| !s.trim().is_empty());
+    let commit_on_create = cf
+        .get("commit_on_create")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let threshold = |k: &str, default: usize| {
+        cf.get(k)
+            .and_then(|v| v.as_integer())
+            .filter(|n| *n >= 0)
+            .map(|n| n as usize)
+            .unwrap_or(default)
+    };
+    CFConfig {
+        ai_enabled,
+        templates_dir,
+        commit_on_create,
+        validation: validation_from_value(cf.get("validation")),
+        major_threshold: threshold("major_threshold", 200),
+        minor_threshold: threshold("minor_threshold", 20),
+    }
+}
+
+/// Parse the optional `[changeforge.validation]` sub-table.
+fn validation_from_value(section: Option<&Value>) -> ValidationConfig {
+    let section = match section {
+        Some(v) => v,
+        None => return ValidationConfig::default(),
+    };
+    let as_usize = |k: &str| {
+        section
+            .get(k)
+            .and_then(|v| v.as_integer())
+            .filter(|n| *n >= 0)
+            .map(|n| n as usize)
+            .unwrap_or(0)
+    };
+    let forbidden_patterns = section
+        .get("forbidden_patterns")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut allowed_tags_per_type = std::collections::HashMap::new();
+    if let Some(table) = section.get("allowed_tags_per_type").and_then(|v| v.as_table()) {
+        for (change_type, tags) in table {
+            if let Some(arr) = tags.as_array() {
+                let tags: Vec<String> = arr
+                    .iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect();
+                allowed_tags_per_type.insert(change_type.to_uppercase(), tags);
+            }
+        }
+    }
+    ValidationConfig {
+        max_subject_len: as_usize("max_subject_len"),
+        min_message_len: as_usize("min_message_len"),
+        require_module: section
+            .get("require_module")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        forbidden_patterns,
+        allowed_tags_per_type,
+    }
 }
 
 pub fn load_changeforge_config() -> CFConfig {
@@ -95,148 +233,131 @@ pub fn load_changeforge_config() -> CFConfig {
     if let Ok(cfg) = fs::read_to_string("changeforge.toml") {
         if let Ok(toml_cfg) = cfg.parse::<Value>() {
             if let Some(cf) = toml_cfg.get("changeforge") {
-                let ai_enabled = cf
-                    .get("ai_enabled")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(true);
-                let templates_dir = cf
-                    .get("templates_dir")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-                    .filter(|s| !s.trim().is_empty());
-                return CFConfig {
-                    ai_enabled,
-                    templates_dir,
-                    commit_on_create: cf
-                        .get("commit_on_create")
-                        .and_then(|v| v.as_bool())
-                        .unwrap_or(true),
-                };
+                return cf_config_from_value(cf);
             }
         }
     }
     // Fallback to pyproject.toml [tool.changeforge]
-    let route = "pyproject.toml";
-    let config = match fs::read_to_string(route) {
+    let config = match fs::read_to_string("pyproject.toml") {
         Ok(c) => c,
-        Err(_) => {
-            return CFConfig {
-                ai_enabled: true,
-                templates_dir: None,
-                commit_on_create: true,
-            }
-        }
+        Err(_) => return CFConfig::default(),
     };
     let toml_config: Value = match config.parse() {
         Ok(t) => t,
-        Err(_) => {
-            return CFConfig {
-                ai_enabled: true,
-                templates_dir: None,
-                commit_on_create: true,
-            }
-        }
+        Err(_) => return CFConfig::default(),
     };
-    if let Some(tool) = toml_config.get("tool") {
-        if let Some(cf) = tool.get("changeforge") {
-            let ai_enabled = cf
-                .get("ai_enabled")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(true);
-            let templates_dir = cf
-                .get("templates_dir")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string())
-                .filter(|s| !s.trim().is_empty());
-            return CFConfig {
-                ai_enabled,
-                templates_dir,
-                commit_on_create: cf
-                    .get("commit_on_create")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(true),
-            };
-        }
-    }
-    CFConfig {
-        ai_enabled: true,
-        templates_dir: None,
-        commit_on_create: true,
-    }
+    toml_config
+        .get("tool")
+        .and_then(|tool| tool.get("changeforge"))
+        .map(cf_config_from_value)
+        .unwrap_or_default()
 }
 
-pub fn open_path(path: String) -> String {
-    // Open the file
-    let file = match fs::File::open(path.clone()) {
-        Ok(file) => file,
-        Err(e) => {
-            panic!("Error opening file {}: {}.", path, e);
-        }
+/// The built-in legal tags per change type, mirroring the cleaned tag words
+/// produced by the creation prompts. Used when the config does not override
+/// `allowed_tags_per_type`.
+fn builtin_allowed_tags(change_type: &str) -> Vec<String> {
+    let tags: &[&str] = match change_type.to_uppercase().as_str() {
+        "MAJOR" => &["Remove", "Rename", "O", "Behavior"],
+        "MINOR" => &["Feature", "Add", "O", "Deprecated"],
+        "PATCH" => &["Refactor", "Bug", "Optimization", "Tests", "Patch"],
+        _ => &[],
     };
-    // Create the buffer to read the file
-    let reader = BufReader::new(file);
-    // Iterate over the lines in the file to get the version
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            // Verify if the line has the pattern
-            if line.contains("version =") || line.contains("__version__ =") {
-                // Initialize the process extraction
-                let pattern = r#""(\d+\.\d+\.\d+)""#;
-                // Compilar el patrón de expresión regular
-                let re = Regex::new(pattern).unwrap();
-                if let Some(captures) = re.captures(&line) {
-                    if let Some(version) = captures.get(1) {
-                        return version.as_str().to_string();
-                    }
-                } else {
-                    panic!(
-                        "In the line \"{}\" it cannot be found a version number.",
-                        line
-                    );
-                }
+    tags.iter().map(|s| s.to_string()).collect()
+}
+
+/// Validate a changeset against the configured rules, collecting every
+/// violation so the caller can report them all at once.
+pub fn validate_changeset(changeset: &Changeset, config: &ValidationConfig) -> std::result::Result<(), Vec<String>> {
+    let mut violations: Vec<String> = Vec::new();
+
+    // Subject = first line of the message.
+    let subject = changeset.message.lines().next().unwrap_or("");
+    if config.max_subject_len > 0 && subject.chars().count() > config.max_subject_len {
+        violations.push(format!(
+            "Subject is {} characters; the maximum is {}.",
+            subject.chars().count(),
+            config.max_subject_len
+        ));
+    }
+    if config.min_message_len > 0 && changeset.message.chars().count() < config.min_message_len {
+        violations.push(format!(
+            "Message is {} characters; the minimum is {}.",
+            changeset.message.chars().count(),
+            config.min_message_len
+        ));
+    }
+
+    // The tag must be legal for the change type.
+    let allowed = config
+        .allowed_tags_per_type
+        .get(&changeset.change.to_uppercase())
+        .cloned()
+        .unwrap_or_else(|| builtin_allowed_tags(&changeset.change));
+    if !allowed.is_empty() && !allowed.iter().any(|t| t == &changeset.tag) {
+        violations.push(format!(
+            "Tag `{}` is not allowed for a {} change. Allowed: {}.",
+            changeset.tag,
+            changeset.change,
+            allowed.join(", ")
+        ));
+    }
+
+    // Forbidden patterns on the message.
+    for pattern in &config.forbidden_patterns {
+        if let Ok(re) = Regex::new(pattern) {
+            if re.is_match(&changeset.message) {
+                violations.push(format!("Message matches forbidden pattern `{}`.", pattern));
             }
-        } else {
-            panic!("Error reading the file {}.", path);
         }
     }
-    // If it reaches here, then it couldn't find the `version`
-    panic!("Couldn't find the version in the path {}. Try with the following version names: [\"version\", \"__version__\"]", path);
+
+    // A module is required when configured.
+    if config.require_module && changeset.modules.is_empty() {
+        violations.push("A module must be set for this changeset.".to_string());
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
 }
 
-fn update_version_path(new_version: &str) {
-    // Find all version paths
-    let version_paths = find_version_in_file();
-    // Get the current version
-    let current_version = find_version();
+pub fn open_path(version_file: &VersionFile) -> Result<String> {
+    let path_buf = PathBuf::from(&version_file.path);
+    // Read the whole file and apply the configured per-file pattern.
+    let content = fs::read_to_string(&path_buf).map_err(|e| Error::Io(path_buf.clone(), e))?;
+    version_file
+        .pattern
+        .captures(&content)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
+        .ok_or(Error::VersionNotFound(path_buf))
+}
 
-    // Update each file
-    for version_path in version_paths {
-        // Open the file
-        let mut file = match fs::File::open(&version_path) {
-            Ok(file) => file,
-            Err(e) => {
-                panic!("Error opening file {}: {}.", version_path, e);
-            }
-        };
-        // Read the content as a String
-        let mut content = String::new();
-        if let Err(e) = file.read_to_string(&mut content) {
-            panic!("Error reading file {}: {}.", version_path, e);
-        }
-        // Substitute the old version for the new version
-        let updated_content = content.replace(&current_version, new_version);
-        // Reopen the file but this time as writing mode
-        file = match fs::File::create(&version_path) {
-            Ok(file) => file,
-            Err(e) => {
-                panic!("Error creating file {}: {}.", version_path, e);
+pub fn update_version_path(new_version: &str) -> Result<()> {
+    // Find all version files along with their patterns.
+    let version_files = find_version_in_file()?;
+
+    // Update each file using its own pattern to locate the version span.
+    for version_file in &version_files {
+        let path_buf = PathBuf::from(&version_file.path);
+        let content = fs::read_to_string(&path_buf).map_err(|e| Error::Io(path_buf.clone(), e))?;
+        // Replace only capture group 1 (the version) of the first match, leaving
+        // the surrounding manifest syntax untouched.
+        let updated_content = match version_file.pattern.captures(&content) {
+            Some(caps) => {
+                let m = caps.get(1).ok_or_else(|| Error::VersionNotFound(path_buf.clone()))?;
+                format!("{}{}{}", &content[..m.start()], new_version, &content[m.end()..])
             }
+            None => return Err(Error::VersionNotFound(path_buf)),
         };
-        // Write the new file
-        if let Err(e) = file.write_all(updated_content.as_bytes()) {
-            panic!("Error writing to file {}: {}.", version_path, e);
-        }
+        let mut file = fs::File::create(&path_buf).map_err(|e| Error::Io(path_buf.clone(), e))?;
+        file.write_all(updated_content.as_bytes())
+            .map_err(|e| Error::Io(path_buf.clone(), e))?;
     }
+    Ok(())
 }
 
 /// Find the largest version in a list of changesets
@@ -244,20 +365,17 @@ pub fn find_largest_version(changesets: &[Changeset]) -> Option<String> {
     changesets
         .iter()
         .filter_map(|c| parse_version(&c.version)) // Parse the versions
-        .max() // Obtain the largest version
-        .map(|(major, minor, patch)| format!("{}.{}.{}", major, minor, patch)) // Convert it back to String
+        // Obtain the largest version following SemVer precedence (build metadata excluded)
+        .max_by_key(|v| (v.major, v.minor, v.patch, v.pre.clone()))
+        .map(|v| v.to_string()) // Convert it back to String
 }
 
-/// Parse a version "MAJOR.MINOR.PATCH" into a tuple (u32, u32, u32)
-fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
-    let parts: Vec<u32> = version
-        .split('.') // Divide into parts
-        .filter_map(|p| p.parse().ok()) // Convert to u32
-        .collect();
-
-    if parts.len() == 3 {
-        Some((parts[0], parts[1], parts[2]))
-    } else {
-        Some((0, 0, 0))
-    }
+/// Parse a full SemVer string (`MAJOR.MINOR.PATCH[-prerelease][+build]`)
+///
+/// Precedence — and therefore the ordering used by [`find_largest_version`] —
+/// follows the SemVer spec: numeric identifiers compare numerically, a
+/// prerelease version ranks below its associated release, and build metadata
+/// is ignored when ordering.
+fn parse_version(version: &str) -> Option<Version> {
+    Version::parse(version).ok()
 }